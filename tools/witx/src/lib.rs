@@ -0,0 +1,5 @@
+pub mod parser;
+pub mod print;
+pub mod resolve;
+pub mod validate;
+pub mod visit;
@@ -18,13 +18,20 @@ mod kw {
     pub use wast::kw::{export, func, import, memory, module, param, result};
 
     wast::custom_keyword!(array);
+    wast::custom_keyword!(case);
+    wast::custom_keyword!(r#const = "const");
     wast::custom_keyword!(const_pointer);
+    wast::custom_keyword!(error);
+    wast::custom_keyword!(expected);
     wast::custom_keyword!(f32);
     wast::custom_keyword!(f64);
     wast::custom_keyword!(field);
     wast::custom_keyword!(flags);
     wast::custom_keyword!(handle);
+    wast::custom_keyword!(list);
+    wast::custom_keyword!(option);
     wast::custom_keyword!(pointer);
+    wast::custom_keyword!(record);
     wast::custom_keyword!(r#enum = "enum");
     wast::custom_keyword!(r#struct = "struct");
     wast::custom_keyword!(r#union = "union");
@@ -34,11 +41,13 @@ mod kw {
     wast::custom_keyword!(s64);
     wast::custom_keyword!(s8);
     wast::custom_keyword!(string);
+    wast::custom_keyword!(tuple);
     wast::custom_keyword!(typename);
     wast::custom_keyword!(u16);
     wast::custom_keyword!(u32);
     wast::custom_keyword!(u64);
     wast::custom_keyword!(u8);
+    wast::custom_keyword!(variant);
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -184,6 +193,8 @@ pub enum DatatypeIdentSyntax<'a> {
     Array(Box<DatatypeIdentSyntax<'a>>),
     Pointer(Box<DatatypeIdentSyntax<'a>>),
     ConstPointer(Box<DatatypeIdentSyntax<'a>>),
+    List(Box<DatatypeIdentSyntax<'a>>),
+    Tuple(Vec<DatatypeIdentSyntax<'a>>),
     Ident(wast::Id<'a>),
 }
 
@@ -196,6 +207,20 @@ impl<'a> Parse<'a> for DatatypeIdentSyntax<'a> {
                 p.parse::<kw::array>()?;
                 Ok(Box::new(parser.parse()?))
             })?))
+        } else if parser.peek2::<kw::list>() {
+            Ok(DatatypeIdentSyntax::List(parser.parens(|p| {
+                p.parse::<kw::list>()?;
+                Ok(Box::new(parser.parse()?))
+            })?))
+        } else if parser.peek2::<kw::tuple>() {
+            Ok(DatatypeIdentSyntax::Tuple(parser.parens(|p| {
+                p.parse::<kw::tuple>()?;
+                let mut types = Vec::new();
+                while !parser.is_empty() {
+                    types.push(parser.parse()?);
+                }
+                Ok(types)
+            })?))
         } else if parser.peek::<wast::LParen>() {
             parser.parens(|p| {
                 p.parse::<AtWitx>()?;
@@ -312,21 +337,36 @@ impl<'a> Parse<'a> for DeclSyntax<'a> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct TypenameSyntax<'a> {
     pub ident: wast::Id<'a>,
+    pub ident_loc: wast::Span,
     pub def: TypedefSyntax<'a>,
 }
 
 impl<'a> Parse<'a> for TypenameSyntax<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         parser.parse::<kw::typename>()?;
+        let ident_loc = parser.cur_span();
         let ident = parser.parse()?;
         let def = parser.parse()?;
-        Ok(TypenameSyntax { ident, def })
+        Ok(TypenameSyntax {
+            ident,
+            ident_loc,
+            def,
+        })
     }
 }
 
+impl PartialEq for TypenameSyntax<'_> {
+    fn eq(&self, other: &TypenameSyntax<'_>) -> bool {
+        // skip the `ident_loc` field
+        self.ident == other.ident && self.def == other.def
+    }
+}
+
+impl Eq for TypenameSyntax<'_> {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypedefSyntax<'a> {
     Ident(DatatypeIdentSyntax<'a>),
@@ -335,6 +375,12 @@ pub enum TypedefSyntax<'a> {
     Struct(StructSyntax<'a>),
     Union(UnionSyntax<'a>),
     Handle(HandleSyntax<'a>),
+    Variant(VariantSyntax<'a>),
+    Record(RecordSyntax<'a>),
+    List(Box<DatatypeIdentSyntax<'a>>),
+    Tuple(TupleSyntax<'a>),
+    Option(Box<DatatypeIdentSyntax<'a>>),
+    Expected(ExpectedSyntax<'a>),
 }
 
 impl<'a> Parse<'a> for TypedefSyntax<'a> {
@@ -355,6 +401,20 @@ impl<'a> Parse<'a> for TypedefSyntax<'a> {
                 Ok(TypedefSyntax::Union(parser.parse()?))
             } else if l.peek::<kw::handle>() {
                 Ok(TypedefSyntax::Handle(parser.parse()?))
+            } else if l.peek::<kw::variant>() {
+                Ok(TypedefSyntax::Variant(parser.parse()?))
+            } else if l.peek::<kw::record>() {
+                Ok(TypedefSyntax::Record(parser.parse()?))
+            } else if l.peek::<kw::list>() {
+                parser.parse::<kw::list>()?;
+                Ok(TypedefSyntax::List(Box::new(parser.parse()?)))
+            } else if l.peek::<kw::tuple>() {
+                Ok(TypedefSyntax::Tuple(parser.parse()?))
+            } else if l.peek::<kw::option>() {
+                parser.parse::<kw::option>()?;
+                Ok(TypedefSyntax::Option(Box::new(parser.parse()?)))
+            } else if l.peek::<kw::expected>() {
+                Ok(TypedefSyntax::Expected(parser.parse()?))
             } else {
                 Err(l.error())
             }
@@ -362,43 +422,132 @@ impl<'a> Parse<'a> for TypedefSyntax<'a> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct EnumSyntax<'a> {
     pub repr: BuiltinType,
-    pub members: Vec<Documented<'a, wast::Id<'a>>>,
+    pub repr_loc: wast::Span,
+    pub members: Vec<Documented<'a, EnumMemberSyntax<'a>>>,
 }
 
 impl<'a> Parse<'a> for EnumSyntax<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         parser.parse::<kw::r#enum>()?;
+        let repr_loc = parser.cur_span();
         let repr = parser.parse()?;
         let mut members = Vec::new();
         members.push(parser.parse()?);
         while !parser.is_empty() {
             members.push(parser.parse()?);
         }
-        Ok(EnumSyntax { repr, members })
+        Ok(EnumSyntax {
+            repr,
+            repr_loc,
+            members,
+        })
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl PartialEq for EnumSyntax<'_> {
+    fn eq(&self, other: &EnumSyntax<'_>) -> bool {
+        // skip the `repr_loc` field
+        self.repr == other.repr && self.members == other.members
+    }
+}
+
+impl Eq for EnumSyntax<'_> {}
+
+/// A single `enum` member: a bare `$name`, or `(const $name N)` giving it
+/// an explicit discriminant. Members without an explicit value take the
+/// previous member's value plus one (zero for the first member), the same
+/// way a C enum resolves bare members interspersed with `= N`.
+#[derive(Debug, Clone)]
+pub struct EnumMemberSyntax<'a> {
+    pub name: wast::Id<'a>,
+    pub name_loc: wast::Span,
+    pub value: Option<u64>,
+}
+
+impl<'a> Parse<'a> for EnumMemberSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        if parser.peek::<wast::LParen>() {
+            parser.parens(|p| {
+                p.parse::<kw::r#const>()?;
+                let name_loc = p.cur_span();
+                let name = p.parse()?;
+                let value = parse_u64(p)?;
+                Ok(EnumMemberSyntax {
+                    name,
+                    name_loc,
+                    value: Some(value),
+                })
+            })
+        } else {
+            let name_loc = parser.cur_span();
+            let name = parser.parse()?;
+            Ok(EnumMemberSyntax {
+                name,
+                name_loc,
+                value: None,
+            })
+        }
+    }
+}
+
+impl PartialEq for EnumMemberSyntax<'_> {
+    fn eq(&self, other: &EnumMemberSyntax<'_>) -> bool {
+        // skip the `name_loc` field
+        self.name == other.name && self.value == other.value
+    }
+}
+
+impl Eq for EnumMemberSyntax<'_> {}
+
+fn parse_u64(parser: Parser<'_>) -> Result<u64> {
+    parser.step(|cursor| {
+        if let Some((int, rest)) = cursor.integer() {
+            let (s, radix) = int.val();
+            let val = u64::from_str_radix(s, radix)
+                .map_err(|_| cursor.error("invalid enum discriminant value"))?;
+            Ok((val, rest))
+        } else {
+            Err(cursor.error("expected an integer discriminant value"))
+        }
+    })
+}
+
+#[derive(Debug, Clone)]
 pub struct FlagsSyntax<'a> {
     pub repr: BuiltinType,
+    pub repr_loc: wast::Span,
     pub flags: Vec<Documented<'a, wast::Id<'a>>>,
 }
 
 impl<'a> Parse<'a> for FlagsSyntax<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         parser.parse::<kw::flags>()?;
+        let repr_loc = parser.cur_span();
         let repr = parser.parse()?;
         let mut flags = Vec::new();
         while !parser.is_empty() {
             flags.push(parser.parse()?);
         }
-        Ok(FlagsSyntax { repr, flags })
+        Ok(FlagsSyntax {
+            repr,
+            repr_loc,
+            flags,
+        })
     }
 }
 
+impl PartialEq for FlagsSyntax<'_> {
+    fn eq(&self, other: &FlagsSyntax<'_>) -> bool {
+        // skip the `repr_loc` field
+        self.repr == other.repr && self.flags == other.flags
+    }
+}
+
+impl Eq for FlagsSyntax<'_> {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructSyntax<'a> {
     pub fields: Vec<Documented<'a, FieldSyntax<'a>>>,
@@ -416,9 +565,10 @@ impl<'a> Parse<'a> for StructSyntax<'a> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct FieldSyntax<'a> {
     pub name: wast::Id<'a>,
+    pub name_loc: wast::Span,
     pub type_: DatatypeIdentSyntax<'a>,
 }
 
@@ -426,13 +576,27 @@ impl<'a> Parse<'a> for FieldSyntax<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         parser.parens(|p| {
             p.parse::<kw::field>()?;
+            let name_loc = p.cur_span();
             let name = p.parse()?;
             let type_ = p.parse()?;
-            Ok(FieldSyntax { name, type_ })
+            Ok(FieldSyntax {
+                name,
+                name_loc,
+                type_,
+            })
         })
     }
 }
 
+impl PartialEq for FieldSyntax<'_> {
+    fn eq(&self, other: &FieldSyntax<'_>) -> bool {
+        // skip the `name_loc` field
+        self.name == other.name && self.type_ == other.type_
+    }
+}
+
+impl Eq for FieldSyntax<'_> {}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UnionSyntax<'a> {
     pub fields: Vec<Documented<'a, FieldSyntax<'a>>>,
@@ -466,6 +630,99 @@ impl<'a> Parse<'a> for HandleSyntax<'a> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseSyntax<'a> {
+    pub name: wast::Id<'a>,
+    pub type_: Option<DatatypeIdentSyntax<'a>>,
+}
+
+impl<'a> Parse<'a> for CaseSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parens(|p| {
+            p.parse::<kw::case>()?;
+            let name = p.parse()?;
+            let type_ = if p.is_empty() { None } else { Some(p.parse()?) };
+            Ok(CaseSyntax { name, type_ })
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSyntax<'a> {
+    pub cases: Vec<Documented<'a, CaseSyntax<'a>>>,
+}
+
+impl<'a> Parse<'a> for VariantSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::variant>()?;
+        let mut cases = Vec::new();
+        cases.push(parser.parse()?);
+        while !parser.is_empty() {
+            cases.push(parser.parse()?);
+        }
+        Ok(VariantSyntax { cases })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordSyntax<'a> {
+    pub fields: Vec<Documented<'a, FieldSyntax<'a>>>,
+}
+
+impl<'a> Parse<'a> for RecordSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::record>()?;
+        let mut fields = Vec::new();
+        fields.push(parser.parse()?);
+        while !parser.is_empty() {
+            fields.push(parser.parse()?);
+        }
+        Ok(RecordSyntax { fields })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TupleSyntax<'a> {
+    pub types: Vec<DatatypeIdentSyntax<'a>>,
+}
+
+impl<'a> Parse<'a> for TupleSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::tuple>()?;
+        let mut types = Vec::new();
+        while !parser.is_empty() {
+            types.push(parser.parse()?);
+        }
+        Ok(TupleSyntax { types })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedSyntax<'a> {
+    pub ok: Option<DatatypeIdentSyntax<'a>>,
+    pub error: Option<Box<DatatypeIdentSyntax<'a>>>,
+}
+
+impl<'a> Parse<'a> for ExpectedSyntax<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.parse::<kw::expected>()?;
+        let ok = if parser.is_empty() || parser.peek2::<kw::error>() {
+            None
+        } else {
+            Some(parser.parse()?)
+        };
+        let error = if parser.is_empty() {
+            None
+        } else {
+            Some(Box::new(parser.parens(|p| {
+                p.parse::<kw::error>()?;
+                p.parse()
+            })?))
+        };
+        Ok(ExpectedSyntax { ok, error })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModuleSyntax<'a> {
     pub name: wast::Id<'a>,
@@ -603,14 +860,18 @@ impl<'a> Parse<'a> for InterfaceFuncField<'a> {
             let mut l = p.lookahead1();
             if l.peek::<kw::param>() {
                 parser.parse::<kw::param>()?;
+                let name_loc = parser.cur_span();
                 Ok(InterfaceFuncField::Param(FieldSyntax {
                     name: parser.parse()?,
+                    name_loc,
                     type_: parser.parse()?,
                 }))
             } else if l.peek::<kw::result>() {
                 parser.parse::<kw::result>()?;
+                let name_loc = parser.cur_span();
                 Ok(InterfaceFuncField::Result(FieldSyntax {
                     name: parser.parse()?,
+                    name_loc,
                     type_: parser.parse()?,
                 }))
             } else {
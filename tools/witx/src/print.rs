@@ -0,0 +1,366 @@
+//! Turns syntax constructs back into canonical witx source text.
+//!
+//! This is the inverse of `parser`: given a `TopLevelDocument`, `Printer`
+//! walks it and writes out an equivalent s-expression document, preserving
+//! item order and re-emitting `;;;` doc comments above the item they were
+//! attached to. Ordinary (non-doc) comments are not printed, doc comments
+//! are written as `;;;` line comments with trailing whitespace trimmed, and
+//! strings are written without escaping. Parsing the output of
+//! `Printer::print_document` reproduces an AST equal (via `PartialEq`) to
+//! the one printed when the source's comments are all `;;;` line doc
+//! comments without trailing whitespace and its strings need no escaping;
+//! otherwise the comments (or strings) of the reparsed AST can differ.
+
+use crate::parser::*;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Printer {
+    indent_width: usize,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Printer { indent_width: 2 }
+    }
+}
+
+impl Printer {
+    pub fn new(indent_width: usize) -> Self {
+        Printer { indent_width }
+    }
+
+    pub fn print_document(&self, doc: &TopLevelDocument) -> String {
+        let mut out = String::new();
+        for (i, item) in doc.items.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            self.print_documented_top_level(&mut out, item);
+        }
+        out
+    }
+
+    fn indent(&self, depth: usize) -> String {
+        " ".repeat(depth * self.indent_width)
+    }
+
+    fn print_comments(&self, out: &mut String, depth: usize, comments: &CommentSyntax) {
+        for comment in &comments.comments {
+            let comment = comment.trim_end();
+            if let Some(doc) = comment.strip_prefix(';') {
+                let _ = writeln!(out, "{};;;{}", self.indent(depth), doc);
+            }
+        }
+    }
+
+    fn print_documented_top_level(&self, out: &mut String, item: &Documented<TopLevelSyntax>) {
+        self.print_comments(out, 0, &item.comments);
+        self.print_top_level(out, &item.item);
+        out.push('\n');
+    }
+
+    fn print_top_level(&self, out: &mut String, item: &TopLevelSyntax) {
+        match item {
+            TopLevelSyntax::Use(path) => {
+                let _ = writeln!(out, "(use \"{}\")", path);
+            }
+            TopLevelSyntax::Decl(decl) => self.print_decl(out, decl),
+        }
+    }
+
+    fn print_decl(&self, out: &mut String, decl: &DeclSyntax) {
+        match decl {
+            DeclSyntax::Typename(t) => self.print_typename(out, t),
+            DeclSyntax::Module(m) => self.print_module(out, m),
+        }
+    }
+
+    fn print_typename(&self, out: &mut String, t: &TypenameSyntax) {
+        let _ = write!(out, "(typename ${} ", t.ident.name());
+        self.print_typedef(out, 0, &t.def);
+        let _ = writeln!(out, ")");
+    }
+
+    fn print_typedef(&self, out: &mut String, depth: usize, def: &TypedefSyntax) {
+        match def {
+            TypedefSyntax::Ident(ty) => {
+                let _ = write!(out, "{}", self.fmt_datatype(ty));
+            }
+            TypedefSyntax::Enum(e) => {
+                let _ = write!(out, "(enum {}", self.fmt_builtin(e.repr));
+                for member in &e.members {
+                    out.push('\n');
+                    self.print_comments(out, depth + 1, &member.comments);
+                    match member.item.value {
+                        Some(value) => {
+                            let _ = write!(
+                                out,
+                                "{}(const ${} {})",
+                                self.indent(depth + 1),
+                                member.item.name.name(),
+                                value
+                            );
+                        }
+                        None => {
+                            let _ = write!(
+                                out,
+                                "{}${}",
+                                self.indent(depth + 1),
+                                member.item.name.name()
+                            );
+                        }
+                    }
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Flags(f) => {
+                let _ = write!(out, "(flags {}", self.fmt_builtin(f.repr));
+                for flag in &f.flags {
+                    out.push('\n');
+                    self.print_comments(out, depth + 1, &flag.comments);
+                    let _ = write!(out, "{}${}", self.indent(depth + 1), flag.item.name());
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Struct(s) => {
+                let _ = write!(out, "(struct");
+                for field in &s.fields {
+                    out.push('\n');
+                    self.print_field(out, depth + 1, field);
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Union(u) => {
+                let _ = write!(out, "(union");
+                for field in &u.fields {
+                    out.push('\n');
+                    self.print_field(out, depth + 1, field);
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Handle(h) => {
+                let _ = write!(out, "(handle");
+                for supertype in &h.supertypes {
+                    let _ = write!(out, " ${}", supertype.name());
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Variant(v) => {
+                let _ = write!(out, "(variant");
+                for case in &v.cases {
+                    out.push('\n');
+                    self.print_case(out, depth + 1, case);
+                }
+                out.push(')');
+            }
+            TypedefSyntax::Record(r) => {
+                let _ = write!(out, "(record");
+                for field in &r.fields {
+                    out.push('\n');
+                    self.print_field(out, depth + 1, field);
+                }
+                out.push(')');
+            }
+            TypedefSyntax::List(ty) => {
+                let _ = write!(out, "(list {})", self.fmt_datatype(ty));
+            }
+            TypedefSyntax::Tuple(t) => {
+                let _ = write!(out, "(tuple {})", self.fmt_datatype_list(&t.types));
+            }
+            TypedefSyntax::Option(ty) => {
+                let _ = write!(out, "(option {})", self.fmt_datatype(ty));
+            }
+            TypedefSyntax::Expected(e) => {
+                let _ = write!(out, "(expected");
+                if let Some(ok) = &e.ok {
+                    let _ = write!(out, " {}", self.fmt_datatype(ok));
+                }
+                if let Some(error) = &e.error {
+                    let _ = write!(out, " (error {})", self.fmt_datatype(error));
+                }
+                out.push(')');
+            }
+        }
+    }
+
+    fn print_field(&self, out: &mut String, depth: usize, field: &Documented<FieldSyntax>) {
+        self.print_comments(out, depth, &field.comments);
+        let _ = write!(
+            out,
+            "{}(field ${} {})",
+            self.indent(depth),
+            field.item.name.name(),
+            self.fmt_datatype(&field.item.type_)
+        );
+    }
+
+    fn print_case(&self, out: &mut String, depth: usize, case: &Documented<CaseSyntax>) {
+        self.print_comments(out, depth, &case.comments);
+        let _ = write!(out, "{}(case ${}", self.indent(depth), case.item.name.name());
+        if let Some(ty) = &case.item.type_ {
+            let _ = write!(out, " {}", self.fmt_datatype(ty));
+        }
+        out.push(')');
+    }
+
+    fn print_module(&self, out: &mut String, m: &ModuleSyntax) {
+        let _ = writeln!(out, "(module ${}", m.name.name());
+        for decl in &m.decls {
+            self.print_comments(out, 1, &decl.comments);
+            let _ = write!(out, "{}", self.indent(1));
+            self.print_module_decl(out, &decl.item);
+            out.push('\n');
+        }
+        let _ = writeln!(out, ")");
+    }
+
+    fn print_module_decl(&self, out: &mut String, decl: &ModuleDeclSyntax) {
+        match decl {
+            ModuleDeclSyntax::Import(i) => self.print_import(out, i),
+            ModuleDeclSyntax::Func(f) => self.print_interface_func(out, f),
+        }
+    }
+
+    fn print_import(&self, out: &mut String, i: &ModuleImportSyntax) {
+        match i.type_ {
+            ImportTypeSyntax::Memory => {
+                let _ = write!(out, "(import \"{}\" (memory))", i.name);
+            }
+        }
+    }
+
+    fn print_interface_func(&self, out: &mut String, f: &InterfaceFuncSyntax) {
+        let _ = write!(out, "(@interface func (export \"{}\")", f.export);
+        for param in &f.params {
+            out.push('\n');
+            self.print_comments(out, 2, &param.comments);
+            let _ = write!(
+                out,
+                "{}(param ${} {})",
+                self.indent(2),
+                param.item.name.name(),
+                self.fmt_datatype(&param.item.type_)
+            );
+        }
+        for result in &f.results {
+            out.push('\n');
+            self.print_comments(out, 2, &result.comments);
+            let _ = write!(
+                out,
+                "{}(result ${} {})",
+                self.indent(2),
+                result.item.name.name(),
+                self.fmt_datatype(&result.item.type_)
+            );
+        }
+        out.push(')');
+    }
+
+    fn fmt_datatype_list(&self, types: &[DatatypeIdentSyntax]) -> String {
+        types
+            .iter()
+            .map(|ty| self.fmt_datatype(ty))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn fmt_datatype(&self, ty: &DatatypeIdentSyntax) -> String {
+        match ty {
+            DatatypeIdentSyntax::Builtin(b) => self.fmt_builtin(*b).to_string(),
+            DatatypeIdentSyntax::Array(inner) => format!("(array {})", self.fmt_datatype(inner)),
+            DatatypeIdentSyntax::Pointer(inner) => {
+                format!("(@witx pointer {})", self.fmt_datatype(inner))
+            }
+            DatatypeIdentSyntax::ConstPointer(inner) => {
+                format!("(@witx const_pointer {})", self.fmt_datatype(inner))
+            }
+            DatatypeIdentSyntax::List(inner) => format!("(list {})", self.fmt_datatype(inner)),
+            DatatypeIdentSyntax::Tuple(types) => format!("(tuple {})", self.fmt_datatype_list(types)),
+            DatatypeIdentSyntax::Ident(id) => format!("${}", id.name()),
+        }
+    }
+
+    fn fmt_builtin(&self, b: BuiltinType) -> &'static str {
+        match b {
+            BuiltinType::String => "string",
+            BuiltinType::U8 => "u8",
+            BuiltinType::U16 => "u16",
+            BuiltinType::U32 => "u32",
+            BuiltinType::U64 => "u64",
+            BuiltinType::S8 => "s8",
+            BuiltinType::S16 => "s16",
+            BuiltinType::S32 => "s32",
+            BuiltinType::S64 => "s64",
+            BuiltinType::F32 => "f32",
+            BuiltinType::F64 => "f64",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(source: &str) {
+        let buf1 = wast::parser::ParseBuffer::new(source).expect("parse buffer");
+        let doc1 =
+            wast::parser::parse::<TopLevelDocument>(&buf1).expect("parse original document");
+
+        let printed = Printer::default().print_document(&doc1);
+
+        let buf2 = wast::parser::ParseBuffer::new(&printed).expect("parse buffer for printed text");
+        let doc2 = wast::parser::parse::<TopLevelDocument>(&buf2)
+            .unwrap_or_else(|e| panic!("printed document failed to reparse: {}\n{}", e, printed));
+
+        assert_eq!(doc1, doc2, "printed text:\n{}", printed);
+    }
+
+    #[test]
+    fn roundtrips_component_model_types() {
+        roundtrip(
+            r#"
+            ;;; The error codes returned by this interface.
+            (typename $errno
+              (enum u16
+                ;;; Success.
+                $success
+                (const $too_big 5)
+                $again))
+
+            (typename $color
+              (variant
+                ;;; No color at all.
+                (case $none)
+                (case $rgb u32)))
+
+            (typename $metadata
+              (record
+                (field $name string)
+                (field $size u32)))
+
+            (typename $chunks (list u8))
+            (typename $point (tuple u32 u32))
+            (typename $maybe_size (option u32))
+            (typename $maybe_errno (expected u32 (error $errno)))
+            "#,
+        );
+    }
+
+    #[test]
+    fn roundtrips_module_and_interface_func() {
+        roundtrip(
+            r#"
+            (typename $errno (enum u16 $success $fail))
+
+            (module $example
+              (import "memory" (memory))
+              ;;; Reads up to `len` bytes.
+              (@interface func (export "read")
+                (param $len u32)
+                (result $err $errno)
+                (result $nread u32)))
+            "#,
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Resolves `(use "path")` directives into a single, flattened document.
+//!
+//! `TopLevelSyntax::Use` only captures the referenced path; this module
+//! does the actual linking, recursively parsing each `use`d file through a
+//! caller-supplied `DocumentLoader`, detecting import cycles, and building
+//! a symbol table from typename to definition that spans file boundaries.
+//!
+//! Parsed documents are kept alive for `'static` by leaking their source
+//! text and parse buffers. witx resolution happens once per tool
+//! invocation (during codegen or validation), so this is simpler than
+//! threading a self-referential arena through the recursion, and the cost
+//! is bounded by the size of the witx documents involved.
+
+use crate::parser::{DeclSyntax, Documented, TopLevelDocument, TopLevelSyntax, TypedefSyntax};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Supplies the raw text of a witx document given the path referenced by a
+/// `(use "...")` directive.
+pub trait DocumentLoader {
+    fn load(&mut self, path: &str) -> Result<String, String>;
+}
+
+impl<F> DocumentLoader for F
+where
+    F: FnMut(&str) -> Result<String, String>,
+{
+    fn load(&mut self, path: &str) -> Result<String, String> {
+        self(path)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The loader couldn't produce the text for a `use`d path.
+    Load { path: String, reason: String },
+    /// The text for a `use`d path didn't parse as a witx document.
+    Parse { path: String, reason: String },
+    /// Following `use` directives would revisit a path already on the
+    /// current import stack. `chain` lists the paths from the root down
+    /// to the `use` that closes the cycle.
+    Cycle { chain: Vec<String> },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::Load { path, reason } => {
+                write!(f, "couldn't load `{}`: {}", path, reason)
+            }
+            ResolveError::Parse { path, reason } => {
+                write!(f, "couldn't parse `{}`: {}", path, reason)
+            }
+            ResolveError::Cycle { chain } => {
+                write!(f, "`use` cycle detected: {}", chain.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// A witx document with every `use` directive resolved and merged in.
+pub struct Document<'a> {
+    /// All declarations, in the order they were first encountered during
+    /// the depth-first walk of `use` directives.
+    pub items: Vec<Documented<'a, TopLevelSyntax<'a>>>,
+    /// Every declared typename, from any file pulled in via `use`.
+    pub typenames: HashMap<&'a str, TypedefSyntax<'a>>,
+}
+
+impl<'a> Document<'a> {
+    /// Looks up the definition of a typename declared anywhere in this
+    /// document or one of its transitive `use`s.
+    pub fn resolve_typename(&self, name: &str) -> Option<&TypedefSyntax<'a>> {
+        self.typenames.get(name)
+    }
+}
+
+/// Recursively resolves `root`'s `use` directives, starting at
+/// `root_path`, using `loader` to fetch the text of each referenced file.
+pub fn resolve(
+    root_path: &str,
+    root: &TopLevelDocument<'static>,
+    loader: &mut dyn DocumentLoader,
+) -> Result<Document<'static>, ResolveError> {
+    let root_path = canonicalize(root_path);
+    let mut resolver = Resolver {
+        loader,
+        stack: vec![root_path.clone()],
+        visited: HashSet::new(),
+        items: Vec::new(),
+        typenames: HashMap::new(),
+    };
+    resolver.visited.insert(root_path);
+    resolver.merge(root)?;
+    Ok(Document {
+        items: resolver.items,
+        typenames: resolver.typenames,
+    })
+}
+
+struct Resolver<'a> {
+    loader: &'a mut dyn DocumentLoader,
+    stack: Vec<String>,
+    visited: HashSet<String>,
+    items: Vec<Documented<'static, TopLevelSyntax<'static>>>,
+    typenames: HashMap<&'static str, TypedefSyntax<'static>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn merge(&mut self, doc: &TopLevelDocument<'static>) -> Result<(), ResolveError> {
+        for item in &doc.items {
+            match &item.item {
+                TopLevelSyntax::Use(path) => self.merge_use(path)?,
+                TopLevelSyntax::Decl(decl) => {
+                    if let DeclSyntax::Typename(t) = decl {
+                        self.typenames.insert(t.ident.name(), t.def.clone());
+                    }
+                    self.items.push(item.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_use(&mut self, path: &str) -> Result<(), ResolveError> {
+        let canonical = canonicalize(path);
+        if self.stack.contains(&canonical) {
+            let mut chain = self.stack.clone();
+            chain.push(canonical);
+            return Err(ResolveError::Cycle { chain });
+        }
+        if !self.visited.insert(canonical.clone()) {
+            // Already merged via another spelling of the same path; dedupe
+            // rather than re-importing the same declarations twice.
+            return Ok(());
+        }
+
+        let source = self
+            .loader
+            .load(path)
+            .map_err(|reason| ResolveError::Load {
+                path: path.to_string(),
+                reason,
+            })?;
+        let doc = parse_leaked(path, source)?;
+
+        self.stack.push(canonical);
+        self.merge(doc)?;
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// Lexically normalizes a `use` path so that different spellings of the
+/// same file (`./a.witx` vs `sub/../a.witx`) collapse to the same string
+/// before being used as a `stack`/`visited` key. This is purely textual:
+/// there's no guarantee the path refers to a real filesystem entry (the
+/// caller-supplied `DocumentLoader` may synthesize documents), so we can't
+/// call out to `std::fs::canonicalize`.
+fn canonicalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            segment => parts.push(segment),
+        }
+    }
+    parts.join("/")
+}
+
+/// Parses `source` as a witx document, leaking its backing storage (the
+/// source text and the `wast` parse buffer built from it) so the result
+/// can live for `'static`. See the module documentation for why that's an
+/// acceptable tradeoff for this one-shot resolution pass.
+fn parse_leaked(
+    path: &str,
+    source: String,
+) -> Result<&'static TopLevelDocument<'static>, ResolveError> {
+    let source: &'static str = Box::leak(source.into_boxed_str());
+    let buf: &'static wast::parser::ParseBuffer<'static> = Box::leak(Box::new(
+        wast::parser::ParseBuffer::new(source).map_err(|e| ResolveError::Parse {
+            path: path.to_string(),
+            reason: e.to_string(),
+        })?,
+    ));
+    let doc = wast::parser::parse::<TopLevelDocument>(buf).map_err(|e| ResolveError::Parse {
+        path: path.to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(Box::leak(Box::new(doc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn parse_root(source: &str) -> &'static TopLevelDocument<'static> {
+        let source: &'static str = Box::leak(source.to_string().into_boxed_str());
+        let buf: &'static wast::parser::ParseBuffer<'static> = Box::leak(Box::new(
+            wast::parser::ParseBuffer::new(source).expect("parse buffer"),
+        ));
+        let doc = wast::parser::parse::<TopLevelDocument>(buf).expect("parse document");
+        Box::leak(Box::new(doc))
+    }
+
+    #[test]
+    fn dedupes_use_paths_that_canonicalize_to_the_same_file() {
+        let root = parse_root(
+            r#"
+            (use "./common.witx")
+            (use "sub/../common.witx")
+            "#,
+        );
+        let mut files = HashMap::new();
+        files.insert(
+            "common.witx".to_string(),
+            "(typename $errno (enum u16 $success $fail))".to_string(),
+        );
+        let doc = resolve("root.witx", root, &mut |path: &str| {
+            files
+                .get(&canonicalize(path))
+                .cloned()
+                .ok_or_else(|| format!("no such file: {}", path))
+        })
+        .expect("resolves without error");
+
+        assert_eq!(doc.typenames.len(), 1);
+        assert_eq!(doc.items.len(), 1);
+    }
+
+    #[test]
+    fn detects_use_cycle() {
+        let root = parse_root(r#"(use "a.witx")"#);
+        let mut files = HashMap::new();
+        files.insert("a.witx".to_string(), "(use \"root.witx\")".to_string());
+        let err = resolve("root.witx", root, &mut |path: &str| {
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| format!("no such file: {}", path))
+        })
+        .expect_err("should detect cycle");
+        match err {
+            ResolveError::Cycle { chain } => {
+                assert_eq!(
+                    chain,
+                    vec![
+                        "root.witx".to_string(),
+                        "a.witx".to_string(),
+                        "root.witx".to_string()
+                    ]
+                );
+            }
+            other => panic!("expected cycle error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Semantic validation for a parsed witx document.
+//!
+//! The parser in `parser` only enforces grammar: it happily accepts
+//! documents that are structurally valid but semantically broken, e.g. an
+//! `enum` with a `string` repr, a reference to a typename that was never
+//! declared, or a struct with two fields named the same thing. `validate`
+//! walks a `TopLevelDocument` and reports every such problem it can find
+//! as a `Diagnostic` carrying the `wast::Span` of the offending syntax, so
+//! a renderer (`codespan`, `miette`, ...) can point straight at the source.
+
+use crate::parser::{
+    BuiltinType, DatatypeIdentSyntax, DeclSyntax, Documented, EnumSyntax, FieldSyntax,
+    InterfaceFuncSyntax, ModuleDeclSyntax, TopLevelDocument, TopLevelSyntax, TypedefSyntax,
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub span: wast::Span,
+    pub message: String,
+}
+
+impl PartialEq for Diagnostic {
+    fn eq(&self, other: &Self) -> bool {
+        // skip the `span` field
+        self.message == other.message
+    }
+}
+
+impl Eq for Diagnostic {}
+
+pub fn validate<'a>(doc: &'a TopLevelDocument<'a>) -> Result<(), Vec<Diagnostic>> {
+    let symbols = typename_table(doc);
+    let mut diagnostics = Vec::new();
+
+    for item in &doc.items {
+        match &item.item {
+            TopLevelSyntax::Decl(DeclSyntax::Typename(t)) => {
+                check_typedef(&t.def, &symbols, &mut diagnostics);
+            }
+            TopLevelSyntax::Decl(DeclSyntax::Module(m)) => {
+                for decl in &m.decls {
+                    if let ModuleDeclSyntax::Func(f) = &decl.item {
+                        check_interface_func(f, &symbols, &mut diagnostics);
+                    }
+                }
+            }
+            TopLevelSyntax::Use(_) => {}
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn typename_table<'a>(doc: &'a TopLevelDocument<'a>) -> HashMap<&'a str, &'a TypedefSyntax<'a>> {
+    let mut symbols = HashMap::new();
+    for item in &doc.items {
+        if let TopLevelSyntax::Decl(DeclSyntax::Typename(t)) = &item.item {
+            symbols.insert(t.ident.name(), &t.def);
+        }
+    }
+    symbols
+}
+
+fn check_typedef<'a>(
+    def: &'a TypedefSyntax<'a>,
+    symbols: &HashMap<&'a str, &'a TypedefSyntax<'a>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match def {
+        TypedefSyntax::Ident(ty) => check_datatype_ident(ty, symbols, diagnostics),
+        TypedefSyntax::Enum(e) => {
+            if !is_integer(e.repr) {
+                diagnostics.push(Diagnostic {
+                    span: e.repr_loc,
+                    message: format!("enum repr must be an integer type, found {:?}", e.repr),
+                });
+            }
+            check_enum_members(e, diagnostics);
+        }
+        TypedefSyntax::Flags(f) => {
+            if !is_integer(f.repr) {
+                diagnostics.push(Diagnostic {
+                    span: f.repr_loc,
+                    message: format!("flags repr must be an integer type, found {:?}", f.repr),
+                });
+            }
+            check_duplicate_ids(f.flags.iter().map(|m| &m.item), "flag", diagnostics);
+        }
+        TypedefSyntax::Struct(s) => {
+            check_duplicate_fields(&s.fields, "struct field", diagnostics);
+            for field in &s.fields {
+                check_datatype_ident(&field.item.type_, symbols, diagnostics);
+            }
+        }
+        TypedefSyntax::Union(u) => {
+            check_duplicate_fields(&u.fields, "union field", diagnostics);
+            for field in &u.fields {
+                check_datatype_ident(&field.item.type_, symbols, diagnostics);
+            }
+        }
+        TypedefSyntax::Record(r) => {
+            check_duplicate_fields(&r.fields, "record field", diagnostics);
+            for field in &r.fields {
+                check_datatype_ident(&field.item.type_, symbols, diagnostics);
+            }
+        }
+        TypedefSyntax::Handle(h) => {
+            for supertype in &h.supertypes {
+                match symbols.get(supertype.name()) {
+                    Some(TypedefSyntax::Handle(_)) => {}
+                    Some(_) => diagnostics.push(Diagnostic {
+                        span: supertype.span(),
+                        message: format!(
+                            "handle supertype `{}` does not refer to a handle type",
+                            supertype.name()
+                        ),
+                    }),
+                    None => diagnostics.push(Diagnostic {
+                        span: supertype.span(),
+                        message: format!("handle supertype `{}` is not declared", supertype.name()),
+                    }),
+                }
+            }
+        }
+        TypedefSyntax::Variant(v) => {
+            let mut seen = HashSet::new();
+            for case in &v.cases {
+                if !seen.insert(case.item.name.name()) {
+                    diagnostics.push(Diagnostic {
+                        span: case.item.name.span(),
+                        message: format!("duplicate variant case `{}`", case.item.name.name()),
+                    });
+                }
+                if let Some(ty) = &case.item.type_ {
+                    check_datatype_ident(ty, symbols, diagnostics);
+                }
+            }
+        }
+        TypedefSyntax::List(ty) | TypedefSyntax::Option(ty) => {
+            check_datatype_ident(ty, symbols, diagnostics);
+        }
+        TypedefSyntax::Tuple(t) => {
+            for ty in &t.types {
+                check_datatype_ident(ty, symbols, diagnostics);
+            }
+        }
+        TypedefSyntax::Expected(e) => {
+            if let Some(ok) = &e.ok {
+                check_datatype_ident(ok, symbols, diagnostics);
+            }
+            if let Some(error) = &e.error {
+                check_datatype_ident(error, symbols, diagnostics);
+            }
+        }
+    }
+}
+
+fn check_datatype_ident<'a>(
+    ty: &'a DatatypeIdentSyntax<'a>,
+    symbols: &HashMap<&'a str, &'a TypedefSyntax<'a>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    match ty {
+        DatatypeIdentSyntax::Ident(id) => {
+            if !symbols.contains_key(id.name()) {
+                diagnostics.push(Diagnostic {
+                    span: id.span(),
+                    message: format!("reference to undeclared typename `{}`", id.name()),
+                });
+            }
+        }
+        DatatypeIdentSyntax::Array(inner)
+        | DatatypeIdentSyntax::Pointer(inner)
+        | DatatypeIdentSyntax::ConstPointer(inner)
+        | DatatypeIdentSyntax::List(inner) => check_datatype_ident(inner, symbols, diagnostics),
+        DatatypeIdentSyntax::Tuple(types) => {
+            for ty in types {
+                check_datatype_ident(ty, symbols, diagnostics);
+            }
+        }
+        DatatypeIdentSyntax::Builtin(_) => {}
+    }
+}
+
+fn check_duplicate_ids<'a>(
+    ids: impl Iterator<Item = &'a wast::Id<'a>>,
+    what: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.name()) {
+            diagnostics.push(Diagnostic {
+                span: id.span(),
+                message: format!("duplicate {} `{}`", what, id.name()),
+            });
+        }
+    }
+}
+
+fn check_duplicate_fields(fields: &[Documented<FieldSyntax>], what: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen = HashSet::new();
+    for field in fields {
+        if !seen.insert(field.item.name.name()) {
+            diagnostics.push(Diagnostic {
+                span: field.item.name_loc,
+                message: format!("duplicate {} `{}`", what, field.item.name.name()),
+            });
+        }
+    }
+}
+
+/// Resolves each member's discriminant (explicit, or one past the previous
+/// member's) and checks it against the enum's repr range, plus flags any
+/// name or discriminant collisions.
+fn check_enum_members(e: &EnumSyntax, diagnostics: &mut Vec<Diagnostic>) {
+    let max = repr_max(e.repr);
+    let mut seen_names = HashSet::new();
+    let mut seen_values = HashSet::new();
+    let mut next_value: u64 = 0;
+
+    for member in &e.members {
+        if !seen_names.insert(member.item.name.name()) {
+            diagnostics.push(Diagnostic {
+                span: member.item.name_loc,
+                message: format!("duplicate enum member `{}`", member.item.name.name()),
+            });
+        }
+
+        let value = member.item.value.unwrap_or(next_value);
+        if value > max {
+            diagnostics.push(Diagnostic {
+                span: member.item.name_loc,
+                message: format!(
+                    "discriminant {} for `{}` overflows {:?}",
+                    value,
+                    member.item.name.name(),
+                    e.repr
+                ),
+            });
+        }
+        if !seen_values.insert(value) {
+            diagnostics.push(Diagnostic {
+                span: member.item.name_loc,
+                message: format!(
+                    "duplicate discriminant {} for `{}`",
+                    value,
+                    member.item.name.name()
+                ),
+            });
+        }
+        next_value = value.wrapping_add(1);
+    }
+}
+
+fn repr_max(repr: BuiltinType) -> u64 {
+    match repr {
+        BuiltinType::U8 => u8::MAX as u64,
+        BuiltinType::U16 => u16::MAX as u64,
+        BuiltinType::U32 => u32::MAX as u64,
+        BuiltinType::U64 => u64::MAX,
+        // Discriminants are parsed as non-negative `u64`s and stored into
+        // the repr's signed width, so a signed repr's legal range tops out
+        // at that width's signed max, not its unsigned one.
+        BuiltinType::S8 => i8::MAX as u64,
+        BuiltinType::S16 => i16::MAX as u64,
+        BuiltinType::S32 => i32::MAX as u64,
+        BuiltinType::S64 => i64::MAX as u64,
+        // Non-integer reprs are flagged separately; treat as unbounded so
+        // we don't pile on a second diagnostic about the same repr.
+        BuiltinType::String | BuiltinType::F32 | BuiltinType::F64 => u64::MAX,
+    }
+}
+
+fn check_interface_func<'a>(
+    f: &'a InterfaceFuncSyntax<'a>,
+    symbols: &HashMap<&'a str, &'a TypedefSyntax<'a>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    check_duplicate_fields(&f.params, "interface func param", diagnostics);
+    check_duplicate_fields(&f.results, "interface func result", diagnostics);
+    for param in &f.params {
+        check_datatype_ident(&param.item.type_, symbols, diagnostics);
+    }
+    for result in &f.results {
+        check_datatype_ident(&result.item.type_, symbols, diagnostics);
+    }
+}
+
+fn is_integer(repr: BuiltinType) -> bool {
+    matches!(
+        repr,
+        BuiltinType::U8
+            | BuiltinType::U16
+            | BuiltinType::U32
+            | BuiltinType::U64
+            | BuiltinType::S8
+            | BuiltinType::S16
+            | BuiltinType::S32
+            | BuiltinType::S64
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> TopLevelDocument {
+        let buf = Box::leak(Box::new(
+            wast::parser::ParseBuffer::new(source).expect("parse buffer"),
+        ));
+        wast::parser::parse::<TopLevelDocument>(buf).expect("parse document")
+    }
+
+    #[test]
+    fn accepts_interface_func_with_declared_types() {
+        let doc = parse(
+            r#"
+            (typename $errno (enum u16 $success $fail))
+            (module $example
+              (import "memory" (memory))
+              (@interface func (export "read")
+                (param $len u32)
+                (result $err $errno)))
+            "#,
+        );
+        assert_eq!(validate(&doc), Ok(()));
+    }
+
+    #[test]
+    fn rejects_interface_func_param_referencing_undeclared_typename() {
+        let doc = parse(
+            r#"
+            (module $example
+              (import "memory" (memory))
+              (@interface func (export "read")
+                (param $x $undeclared)))
+            "#,
+        );
+        let diagnostics = validate(&doc).expect_err("should reject undeclared typename");
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("undeclared typename `undeclared`")));
+    }
+
+    #[test]
+    fn rejects_interface_func_result_referencing_undeclared_typename() {
+        let doc = parse(
+            r#"
+            (module $example
+              (import "memory" (memory))
+              (@interface func (export "read")
+                (result $x $undeclared)))
+            "#,
+        );
+        let diagnostics = validate(&doc).expect_err("should reject undeclared typename");
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("undeclared typename `undeclared`")));
+    }
+
+    #[test]
+    fn accepts_signed_enum_discriminant_within_range() {
+        let doc = parse(r#"(typename $e (enum s8 (const $a 127)))"#);
+        assert_eq!(validate(&doc), Ok(()));
+    }
+
+    #[test]
+    fn rejects_signed_enum_discriminant_overflowing_repr() {
+        let doc = parse(r#"(typename $e (enum s8 (const $a 200)))"#);
+        let diagnostics = validate(&doc).expect_err("should reject overflowing discriminant");
+        assert!(diagnostics.iter().any(|d| d.message.contains("overflows")));
+    }
+
+    #[test]
+    fn rejects_duplicate_enum_discriminant() {
+        let doc = parse(r#"(typename $e (enum u8 (const $a 1) (const $b 1)))"#);
+        let diagnostics = validate(&doc).expect_err("should reject duplicate discriminant");
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("duplicate discriminant")));
+    }
+}
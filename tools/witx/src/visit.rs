@@ -0,0 +1,598 @@
+//! Generic traversal over the syntax tree produced by `parser`.
+//!
+//! `Visit`/`VisitMut` give consumers (validators, codegen, linters) a
+//! default-recursing method per node type, so walking the whole tree to
+//! collect or rewrite something doesn't require re-deriving its shape.
+//! Override just the methods you care about; the default bodies (the
+//! free `walk_*`/`walk_*_mut` functions) recurse into children for you.
+//!
+//! Modeled on `syn`'s generated `visit`/`visit_mut` modules.
+
+use crate::parser::{
+    BuiltinType, CaseSyntax, DatatypeIdentSyntax, DeclSyntax, EnumSyntax, ExpectedSyntax,
+    FieldSyntax, FlagsSyntax, HandleSyntax, InterfaceFuncSyntax, ModuleDeclSyntax,
+    ModuleImportSyntax, ModuleSyntax, RecordSyntax, StructSyntax, TopLevelDocument,
+    TopLevelSyntax, TupleSyntax, TypedefSyntax, TypenameSyntax, UnionSyntax, VariantSyntax,
+};
+
+pub trait Visit<'ast> {
+    fn visit_top_level_document(&mut self, node: &'ast TopLevelDocument<'ast>) {
+        walk_top_level_document(self, node);
+    }
+    fn visit_top_level_syntax(&mut self, node: &'ast TopLevelSyntax<'ast>) {
+        walk_top_level_syntax(self, node);
+    }
+    fn visit_decl_syntax(&mut self, node: &'ast DeclSyntax<'ast>) {
+        walk_decl_syntax(self, node);
+    }
+    fn visit_typename_syntax(&mut self, node: &'ast TypenameSyntax<'ast>) {
+        walk_typename_syntax(self, node);
+    }
+    fn visit_typedef_syntax(&mut self, node: &'ast TypedefSyntax<'ast>) {
+        walk_typedef_syntax(self, node);
+    }
+    fn visit_datatype_ident_syntax(&mut self, node: &'ast DatatypeIdentSyntax<'ast>) {
+        walk_datatype_ident_syntax(self, node);
+    }
+    fn visit_enum_syntax(&mut self, node: &'ast EnumSyntax<'ast>) {
+        walk_enum_syntax(self, node);
+    }
+    fn visit_flags_syntax(&mut self, node: &'ast FlagsSyntax<'ast>) {
+        walk_flags_syntax(self, node);
+    }
+    fn visit_struct_syntax(&mut self, node: &'ast StructSyntax<'ast>) {
+        walk_struct_syntax(self, node);
+    }
+    fn visit_union_syntax(&mut self, node: &'ast UnionSyntax<'ast>) {
+        walk_union_syntax(self, node);
+    }
+    fn visit_handle_syntax(&mut self, node: &'ast HandleSyntax<'ast>) {
+        walk_handle_syntax(self, node);
+    }
+    fn visit_variant_syntax(&mut self, node: &'ast VariantSyntax<'ast>) {
+        walk_variant_syntax(self, node);
+    }
+    fn visit_case_syntax(&mut self, node: &'ast CaseSyntax<'ast>) {
+        walk_case_syntax(self, node);
+    }
+    fn visit_record_syntax(&mut self, node: &'ast RecordSyntax<'ast>) {
+        walk_record_syntax(self, node);
+    }
+    fn visit_tuple_syntax(&mut self, node: &'ast TupleSyntax<'ast>) {
+        walk_tuple_syntax(self, node);
+    }
+    fn visit_expected_syntax(&mut self, node: &'ast ExpectedSyntax<'ast>) {
+        walk_expected_syntax(self, node);
+    }
+    fn visit_field_syntax(&mut self, node: &'ast FieldSyntax<'ast>) {
+        walk_field_syntax(self, node);
+    }
+    fn visit_module_syntax(&mut self, node: &'ast ModuleSyntax<'ast>) {
+        walk_module_syntax(self, node);
+    }
+    fn visit_module_decl_syntax(&mut self, node: &'ast ModuleDeclSyntax<'ast>) {
+        walk_module_decl_syntax(self, node);
+    }
+    fn visit_module_import_syntax(&mut self, _node: &'ast ModuleImportSyntax<'ast>) {}
+    fn visit_interface_func_syntax(&mut self, node: &'ast InterfaceFuncSyntax<'ast>) {
+        walk_interface_func_syntax(self, node);
+    }
+    fn visit_id(&mut self, _node: &'ast wast::Id<'ast>) {}
+    fn visit_builtin_type(&mut self, _node: &'ast BuiltinType) {}
+}
+
+pub fn walk_top_level_document<'ast, V>(v: &mut V, node: &'ast TopLevelDocument<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for item in &node.items {
+        v.visit_top_level_syntax(&item.item);
+    }
+}
+
+pub fn walk_top_level_syntax<'ast, V>(v: &mut V, node: &'ast TopLevelSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        TopLevelSyntax::Use(_) => {}
+        TopLevelSyntax::Decl(decl) => v.visit_decl_syntax(decl),
+    }
+}
+
+pub fn walk_decl_syntax<'ast, V>(v: &mut V, node: &'ast DeclSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        DeclSyntax::Typename(t) => v.visit_typename_syntax(t),
+        DeclSyntax::Module(m) => v.visit_module_syntax(m),
+    }
+}
+
+pub fn walk_typename_syntax<'ast, V>(v: &mut V, node: &'ast TypenameSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_id(&node.ident);
+    v.visit_typedef_syntax(&node.def);
+}
+
+pub fn walk_typedef_syntax<'ast, V>(v: &mut V, node: &'ast TypedefSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        TypedefSyntax::Ident(ty) => v.visit_datatype_ident_syntax(ty),
+        TypedefSyntax::Enum(e) => v.visit_enum_syntax(e),
+        TypedefSyntax::Flags(f) => v.visit_flags_syntax(f),
+        TypedefSyntax::Struct(s) => v.visit_struct_syntax(s),
+        TypedefSyntax::Union(u) => v.visit_union_syntax(u),
+        TypedefSyntax::Handle(h) => v.visit_handle_syntax(h),
+        TypedefSyntax::Variant(x) => v.visit_variant_syntax(x),
+        TypedefSyntax::Record(x) => v.visit_record_syntax(x),
+        TypedefSyntax::List(ty) => v.visit_datatype_ident_syntax(ty),
+        TypedefSyntax::Tuple(x) => v.visit_tuple_syntax(x),
+        TypedefSyntax::Option(ty) => v.visit_datatype_ident_syntax(ty),
+        TypedefSyntax::Expected(x) => v.visit_expected_syntax(x),
+    }
+}
+
+pub fn walk_datatype_ident_syntax<'ast, V>(v: &mut V, node: &'ast DatatypeIdentSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        DatatypeIdentSyntax::Builtin(b) => v.visit_builtin_type(b),
+        DatatypeIdentSyntax::Array(ty)
+        | DatatypeIdentSyntax::Pointer(ty)
+        | DatatypeIdentSyntax::ConstPointer(ty)
+        | DatatypeIdentSyntax::List(ty) => v.visit_datatype_ident_syntax(ty),
+        DatatypeIdentSyntax::Tuple(types) => {
+            for ty in types {
+                v.visit_datatype_ident_syntax(ty);
+            }
+        }
+        DatatypeIdentSyntax::Ident(id) => v.visit_id(id),
+    }
+}
+
+pub fn walk_enum_syntax<'ast, V>(v: &mut V, node: &'ast EnumSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_builtin_type(&node.repr);
+    for member in &node.members {
+        v.visit_id(&member.item.name);
+    }
+}
+
+pub fn walk_flags_syntax<'ast, V>(v: &mut V, node: &'ast FlagsSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_builtin_type(&node.repr);
+    for flag in &node.flags {
+        v.visit_id(&flag.item);
+    }
+}
+
+pub fn walk_struct_syntax<'ast, V>(v: &mut V, node: &'ast StructSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for field in &node.fields {
+        v.visit_field_syntax(&field.item);
+    }
+}
+
+pub fn walk_union_syntax<'ast, V>(v: &mut V, node: &'ast UnionSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for field in &node.fields {
+        v.visit_field_syntax(&field.item);
+    }
+}
+
+pub fn walk_handle_syntax<'ast, V>(v: &mut V, node: &'ast HandleSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for supertype in &node.supertypes {
+        v.visit_id(supertype);
+    }
+}
+
+pub fn walk_variant_syntax<'ast, V>(v: &mut V, node: &'ast VariantSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for case in &node.cases {
+        v.visit_case_syntax(&case.item);
+    }
+}
+
+pub fn walk_case_syntax<'ast, V>(v: &mut V, node: &'ast CaseSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_id(&node.name);
+    if let Some(ty) = &node.type_ {
+        v.visit_datatype_ident_syntax(ty);
+    }
+}
+
+pub fn walk_record_syntax<'ast, V>(v: &mut V, node: &'ast RecordSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for field in &node.fields {
+        v.visit_field_syntax(&field.item);
+    }
+}
+
+pub fn walk_tuple_syntax<'ast, V>(v: &mut V, node: &'ast TupleSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for ty in &node.types {
+        v.visit_datatype_ident_syntax(ty);
+    }
+}
+
+pub fn walk_expected_syntax<'ast, V>(v: &mut V, node: &'ast ExpectedSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    if let Some(ok) = &node.ok {
+        v.visit_datatype_ident_syntax(ok);
+    }
+    if let Some(error) = &node.error {
+        v.visit_datatype_ident_syntax(error);
+    }
+}
+
+pub fn walk_field_syntax<'ast, V>(v: &mut V, node: &'ast FieldSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_id(&node.name);
+    v.visit_datatype_ident_syntax(&node.type_);
+}
+
+pub fn walk_module_syntax<'ast, V>(v: &mut V, node: &'ast ModuleSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    v.visit_id(&node.name);
+    for decl in &node.decls {
+        v.visit_module_decl_syntax(&decl.item);
+    }
+}
+
+pub fn walk_module_decl_syntax<'ast, V>(v: &mut V, node: &'ast ModuleDeclSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    match node {
+        ModuleDeclSyntax::Import(i) => v.visit_module_import_syntax(i),
+        ModuleDeclSyntax::Func(f) => v.visit_interface_func_syntax(f),
+    }
+}
+
+pub fn walk_interface_func_syntax<'ast, V>(v: &mut V, node: &'ast InterfaceFuncSyntax<'ast>)
+where
+    V: Visit<'ast> + ?Sized,
+{
+    for param in &node.params {
+        v.visit_field_syntax(&param.item);
+    }
+    for result in &node.results {
+        v.visit_field_syntax(&result.item);
+    }
+}
+
+pub trait VisitMut {
+    fn visit_top_level_document_mut(&mut self, node: &mut TopLevelDocument<'_>) {
+        walk_top_level_document_mut(self, node);
+    }
+    fn visit_top_level_syntax_mut(&mut self, node: &mut TopLevelSyntax<'_>) {
+        walk_top_level_syntax_mut(self, node);
+    }
+    fn visit_decl_syntax_mut(&mut self, node: &mut DeclSyntax<'_>) {
+        walk_decl_syntax_mut(self, node);
+    }
+    fn visit_typename_syntax_mut(&mut self, node: &mut TypenameSyntax<'_>) {
+        walk_typename_syntax_mut(self, node);
+    }
+    fn visit_typedef_syntax_mut(&mut self, node: &mut TypedefSyntax<'_>) {
+        walk_typedef_syntax_mut(self, node);
+    }
+    fn visit_datatype_ident_syntax_mut(&mut self, node: &mut DatatypeIdentSyntax<'_>) {
+        walk_datatype_ident_syntax_mut(self, node);
+    }
+    fn visit_enum_syntax_mut(&mut self, node: &mut EnumSyntax<'_>) {
+        walk_enum_syntax_mut(self, node);
+    }
+    fn visit_flags_syntax_mut(&mut self, node: &mut FlagsSyntax<'_>) {
+        walk_flags_syntax_mut(self, node);
+    }
+    fn visit_struct_syntax_mut(&mut self, node: &mut StructSyntax<'_>) {
+        walk_struct_syntax_mut(self, node);
+    }
+    fn visit_union_syntax_mut(&mut self, node: &mut UnionSyntax<'_>) {
+        walk_union_syntax_mut(self, node);
+    }
+    fn visit_handle_syntax_mut(&mut self, node: &mut HandleSyntax<'_>) {
+        walk_handle_syntax_mut(self, node);
+    }
+    fn visit_variant_syntax_mut(&mut self, node: &mut VariantSyntax<'_>) {
+        walk_variant_syntax_mut(self, node);
+    }
+    fn visit_case_syntax_mut(&mut self, node: &mut CaseSyntax<'_>) {
+        walk_case_syntax_mut(self, node);
+    }
+    fn visit_record_syntax_mut(&mut self, node: &mut RecordSyntax<'_>) {
+        walk_record_syntax_mut(self, node);
+    }
+    fn visit_tuple_syntax_mut(&mut self, node: &mut TupleSyntax<'_>) {
+        walk_tuple_syntax_mut(self, node);
+    }
+    fn visit_expected_syntax_mut(&mut self, node: &mut ExpectedSyntax<'_>) {
+        walk_expected_syntax_mut(self, node);
+    }
+    fn visit_field_syntax_mut(&mut self, node: &mut FieldSyntax<'_>) {
+        walk_field_syntax_mut(self, node);
+    }
+    fn visit_module_syntax_mut(&mut self, node: &mut ModuleSyntax<'_>) {
+        walk_module_syntax_mut(self, node);
+    }
+    fn visit_module_decl_syntax_mut(&mut self, node: &mut ModuleDeclSyntax<'_>) {
+        walk_module_decl_syntax_mut(self, node);
+    }
+    fn visit_module_import_syntax_mut(&mut self, _node: &mut ModuleImportSyntax<'_>) {}
+    fn visit_interface_func_syntax_mut(&mut self, node: &mut InterfaceFuncSyntax<'_>) {
+        walk_interface_func_syntax_mut(self, node);
+    }
+    fn visit_id_mut(&mut self, _node: &mut wast::Id<'_>) {}
+    fn visit_builtin_type_mut(&mut self, _node: &mut BuiltinType) {}
+}
+
+pub fn walk_top_level_document_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut TopLevelDocument<'_>,
+) {
+    for item in &mut node.items {
+        v.visit_top_level_syntax_mut(&mut item.item);
+    }
+}
+
+pub fn walk_top_level_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TopLevelSyntax<'_>) {
+    if let TopLevelSyntax::Decl(decl) = node {
+        v.visit_decl_syntax_mut(decl);
+    }
+}
+
+pub fn walk_decl_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut DeclSyntax<'_>) {
+    match node {
+        DeclSyntax::Typename(t) => v.visit_typename_syntax_mut(t),
+        DeclSyntax::Module(m) => v.visit_module_syntax_mut(m),
+    }
+}
+
+pub fn walk_typename_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TypenameSyntax<'_>) {
+    v.visit_id_mut(&mut node.ident);
+    v.visit_typedef_syntax_mut(&mut node.def);
+}
+
+pub fn walk_typedef_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TypedefSyntax<'_>) {
+    match node {
+        TypedefSyntax::Ident(ty) => v.visit_datatype_ident_syntax_mut(ty),
+        TypedefSyntax::Enum(e) => v.visit_enum_syntax_mut(e),
+        TypedefSyntax::Flags(f) => v.visit_flags_syntax_mut(f),
+        TypedefSyntax::Struct(s) => v.visit_struct_syntax_mut(s),
+        TypedefSyntax::Union(u) => v.visit_union_syntax_mut(u),
+        TypedefSyntax::Handle(h) => v.visit_handle_syntax_mut(h),
+        TypedefSyntax::Variant(x) => v.visit_variant_syntax_mut(x),
+        TypedefSyntax::Record(x) => v.visit_record_syntax_mut(x),
+        TypedefSyntax::List(ty) => v.visit_datatype_ident_syntax_mut(ty),
+        TypedefSyntax::Tuple(x) => v.visit_tuple_syntax_mut(x),
+        TypedefSyntax::Option(ty) => v.visit_datatype_ident_syntax_mut(ty),
+        TypedefSyntax::Expected(x) => v.visit_expected_syntax_mut(x),
+    }
+}
+
+pub fn walk_datatype_ident_syntax_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut DatatypeIdentSyntax<'_>,
+) {
+    match node {
+        DatatypeIdentSyntax::Builtin(b) => v.visit_builtin_type_mut(b),
+        DatatypeIdentSyntax::Array(ty)
+        | DatatypeIdentSyntax::Pointer(ty)
+        | DatatypeIdentSyntax::ConstPointer(ty)
+        | DatatypeIdentSyntax::List(ty) => v.visit_datatype_ident_syntax_mut(ty),
+        DatatypeIdentSyntax::Tuple(types) => {
+            for ty in types {
+                v.visit_datatype_ident_syntax_mut(ty);
+            }
+        }
+        DatatypeIdentSyntax::Ident(id) => v.visit_id_mut(id),
+    }
+}
+
+pub fn walk_enum_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut EnumSyntax<'_>) {
+    v.visit_builtin_type_mut(&mut node.repr);
+    for member in &mut node.members {
+        v.visit_id_mut(&mut member.item.name);
+    }
+}
+
+pub fn walk_flags_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut FlagsSyntax<'_>) {
+    v.visit_builtin_type_mut(&mut node.repr);
+    for flag in &mut node.flags {
+        v.visit_id_mut(&mut flag.item);
+    }
+}
+
+pub fn walk_struct_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut StructSyntax<'_>) {
+    for field in &mut node.fields {
+        v.visit_field_syntax_mut(&mut field.item);
+    }
+}
+
+pub fn walk_union_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut UnionSyntax<'_>) {
+    for field in &mut node.fields {
+        v.visit_field_syntax_mut(&mut field.item);
+    }
+}
+
+pub fn walk_handle_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut HandleSyntax<'_>) {
+    for supertype in &mut node.supertypes {
+        v.visit_id_mut(supertype);
+    }
+}
+
+pub fn walk_variant_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut VariantSyntax<'_>) {
+    for case in &mut node.cases {
+        v.visit_case_syntax_mut(&mut case.item);
+    }
+}
+
+pub fn walk_case_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CaseSyntax<'_>) {
+    v.visit_id_mut(&mut node.name);
+    if let Some(ty) = &mut node.type_ {
+        v.visit_datatype_ident_syntax_mut(ty);
+    }
+}
+
+pub fn walk_record_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut RecordSyntax<'_>) {
+    for field in &mut node.fields {
+        v.visit_field_syntax_mut(&mut field.item);
+    }
+}
+
+pub fn walk_tuple_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TupleSyntax<'_>) {
+    for ty in &mut node.types {
+        v.visit_datatype_ident_syntax_mut(ty);
+    }
+}
+
+pub fn walk_expected_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ExpectedSyntax<'_>) {
+    if let Some(ok) = &mut node.ok {
+        v.visit_datatype_ident_syntax_mut(ok);
+    }
+    if let Some(error) = &mut node.error {
+        v.visit_datatype_ident_syntax_mut(error);
+    }
+}
+
+pub fn walk_field_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut FieldSyntax<'_>) {
+    v.visit_id_mut(&mut node.name);
+    v.visit_datatype_ident_syntax_mut(&mut node.type_);
+}
+
+pub fn walk_module_syntax_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ModuleSyntax<'_>) {
+    v.visit_id_mut(&mut node.name);
+    for decl in &mut node.decls {
+        v.visit_module_decl_syntax_mut(&mut decl.item);
+    }
+}
+
+pub fn walk_module_decl_syntax_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut ModuleDeclSyntax<'_>,
+) {
+    match node {
+        ModuleDeclSyntax::Import(i) => v.visit_module_import_syntax_mut(i),
+        ModuleDeclSyntax::Func(f) => v.visit_interface_func_syntax_mut(f),
+    }
+}
+
+pub fn walk_interface_func_syntax_mut<V: VisitMut + ?Sized>(
+    v: &mut V,
+    node: &mut InterfaceFuncSyntax<'_>,
+) {
+    for param in &mut node.params {
+        v.visit_field_syntax_mut(&mut param.item);
+    }
+    for result in &mut node.results {
+        v.visit_field_syntax_mut(&mut result.item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TopLevelDocument;
+
+    fn parse(source: &str) -> TopLevelDocument {
+        let buf = Box::leak(Box::new(
+            wast::parser::ParseBuffer::new(source).expect("parse buffer"),
+        ));
+        wast::parser::parse::<TopLevelDocument>(buf).expect("parse document")
+    }
+
+    #[derive(Default)]
+    struct IdCollector<'ast> {
+        ids: Vec<&'ast str>,
+    }
+
+    impl<'ast> Visit<'ast> for IdCollector<'ast> {
+        fn visit_id(&mut self, node: &'ast wast::Id<'ast>) {
+            self.ids.push(node.name());
+        }
+    }
+
+    #[test]
+    fn visit_walks_every_new_typedef_variant() {
+        let doc = parse(
+            r#"
+            (typename $color (variant (case $none) (case $rgb u32)))
+            (typename $metadata (record (field $name string) (field $size u32)))
+            (typename $chunks (list u8))
+            (typename $point (tuple u32 u32))
+            (typename $maybe_size (option u32))
+            (typename $maybe_errno (expected u32 (error $color)))
+            "#,
+        );
+        let mut collector = IdCollector::default();
+        collector.visit_top_level_document(&doc);
+        assert_eq!(
+            collector.ids,
+            vec![
+                "color",
+                "none",
+                "rgb",
+                "metadata",
+                "name",
+                "size",
+                "chunks",
+                "point",
+                "maybe_size",
+                "maybe_errno",
+                "color",
+            ]
+        );
+    }
+
+    struct NoopRenamer;
+
+    impl VisitMut for NoopRenamer {}
+
+    #[test]
+    fn visit_mut_walks_every_new_typedef_variant_without_panicking() {
+        let mut doc = parse(
+            r#"
+            (typename $color (variant (case $none) (case $rgb u32)))
+            (typename $metadata (record (field $name string) (field $size u32)))
+            (typename $chunks (list u8))
+            (typename $point (tuple u32 u32))
+            (typename $maybe_size (option u32))
+            (typename $maybe_errno (expected u32 (error $color)))
+            "#,
+        );
+        NoopRenamer.visit_top_level_document_mut(&mut doc);
+    }
+}